@@ -46,16 +46,25 @@
 //! }
 //! ```
 
+use std::collections::VecDeque;
+use std::ffi::CStr;
 use std::future::Future;
 use std::io::{Error, ErrorKind, Result};
-use std::os::raw::{c_char, c_void};
+use std::os::raw::{c_char, c_int, c_void};
+use std::os::unix::io::RawFd;
 use std::pin::Pin;
 use std::ptr;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use num_enum::TryFromPrimitive;
 use thiserror::Error;
+use tokio::io::unix::AsyncFd;
 
-pub use libdlpi_sys as sys;
+/// Raw FFI bindings to `libdlpi`.
+pub mod sys;
+
+/// pcap-format packet capture built on [`recv`]/[`recv_async`].
+pub mod capture;
 
 /// Result of a DLPI operation.
 #[repr(i32)]
@@ -123,6 +132,49 @@ impl DropHandle {
     }
 }
 
+struct WalkCallback<'a> {
+    cb: &'a mut dyn FnMut(&str) -> bool,
+}
+
+unsafe extern "C" fn walk_trampoline(
+    linkname: *const c_char,
+    arg: *mut c_void,
+) -> c_int {
+    // dlpi_walk's callback returns boolean_t: B_TRUE (nonzero) stops the
+    // walk, B_FALSE (zero) continues it.
+    if linkname.is_null() || arg.is_null() {
+        return 1;
+    }
+    let wc = &mut *(arg as *mut WalkCallback);
+    let name = CStr::from_ptr(linkname).to_string_lossy();
+    if (wc.cb)(&name) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Enumerate DLPI-capable links on the system, invoking `f` with each link
+/// name found. Return `false` from `f` to stop the walk early.
+pub fn walk_with(mut f: impl FnMut(&str) -> bool) {
+    let mut wc = WalkCallback { cb: &mut f };
+    let arg = &mut wc as *mut WalkCallback as *mut c_void;
+    unsafe { sys::dlpi_walk(walk_trampoline, arg, 0) };
+}
+
+/// Enumerate the names of every DLPI-capable link on the system.
+///
+/// This lets callers discover link names natively instead of shelling out
+/// to `dladm` before [`open`].
+pub fn walk() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    walk_with(|name| {
+        names.push(name.to_string());
+        true
+    });
+    Ok(names)
+}
+
 /// Creates a DLPI link instance.
 pub fn open(linkname: impl AsRef<str>, flags: u32) -> Result<DlpiHandle> {
     let linkname = format!("{}\0", linkname.as_ref());
@@ -201,51 +253,97 @@ pub fn recv(
     Ok((src_read, msg_read))
 }
 
+/// Per-link parameters returned by [`info`].
+#[derive(Debug, Clone)]
+pub struct DlpiInfo {
+    /// DLPI MAC type of this link, e.g. `DL_ETHER`.
+    pub mac_type: u32,
+    /// Smallest service data unit this link will send or receive.
+    pub min_sdu: u32,
+    /// Largest service data unit this link will send or receive.
+    pub max_sdu: u32,
+    /// Current physical (MAC) address of this link.
+    pub physaddr: Vec<u8>,
+    /// Broadcast address of this link.
+    pub bcastaddr: Vec<u8>,
+}
+
+/// Query per-link parameters such as MTU bounds, MAC type, and physical
+/// address for the link associated with the provided handle.
+///
+/// This is the standard way to discover how large a buffer [`recv`] needs or
+/// what source address to stamp on an outgoing frame. `dlpi_info_t` has no
+/// bound-SAP or factory-address field; use [`bind`]'s return value for the
+/// former and [`physaddr`] with [`PhysAddrType::Factory`] for the latter.
+pub fn info(h: DlpiHandle) -> Result<DlpiInfo> {
+    let mut infop = sys::dlpi_info_t::default();
+    let ret = unsafe { sys::dlpi_info(h.0, &mut infop, 0) };
+    check_return(ret)?;
+
+    Ok(DlpiInfo {
+        mac_type: infop.di_mactype,
+        min_sdu: infop.di_min_sdu,
+        max_sdu: infop.di_max_sdu,
+        physaddr: infop.di_physaddr[..infop.di_physaddrlen as usize].to_vec(),
+        bcastaddr: infop.di_bcastaddr[..infop.di_bcastaddrlen as usize]
+            .to_vec(),
+    })
+}
+
 /// A receiver object returned from [`recv_async`] wrapped in a future. Calling
 /// `await` on this object yields the same result as [`recv`].
+///
+/// The underlying DLPI stream fd is registered with the async runtime's
+/// reactor on first poll, so the task is only woken once the fd is actually
+/// readable instead of busy-polling `dlpi_recv`.
 pub struct DlpiRecv<'a> {
     h: DlpiHandle,
     src: &'a mut [u8],
     msg: &'a mut [u8],
     info: Option<&'a mut sys::dlpi_recvinfo_t>,
+    afd: Option<AsyncFd<RawFd>>,
 }
 
 /// An `async` version of [`recv`]. Calling `await` on result yields same
 /// result as [`recv`].
 ///
 /// **`src` must be at least [`sys::DLPI_PHYSADDR_MAX`] in length**.
-/*pub fn recv_async<'a>(
+pub fn recv_async<'a>(
     h: DlpiHandle,
     src: &'a mut [u8],
     msg: &'a mut [u8],
     info: Option<&'a mut sys::dlpi_recvinfo_t>,
 ) -> DlpiRecv<'a> {
-    DlpiRecv::<'a> { h, src, msg, info }
-}
-*/
-
-pub async fn recv_async<'a>(
-    h: DlpiHandle,
-    src: &'a mut [u8],
-    msg: &'a mut [u8],
-    info: Option<&'a mut sys::dlpi_recvinfo_t>,
-) -> Result<(usize, usize)> {
-    let afd = tokio::io::unix::AsyncFd::new(fd(h)?)?;
-    let mut _guard = afd.readable().await?;
-    recv(
-        h, src, msg, 0, // non blocking
-        info,
-    )
+    DlpiRecv::<'a> { h, src, msg, info, afd: None }
 }
 
 impl<'a> Future for DlpiRecv<'a> {
     type Output = Result<(usize, usize)>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let mut src_read = self.src.len();
-        let mut msg_read = self.msg.len();
         let s = self.get_mut();
 
+        if s.afd.is_none() {
+            let raw = match fd(s.h) {
+                Ok(raw) => raw,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            s.afd = Some(match AsyncFd::new(raw) {
+                Ok(afd) => afd,
+                Err(e) => return Poll::Ready(Err(e)),
+            });
+        }
+
+        let mut guard =
+            match s.afd.as_mut().unwrap().poll_read_ready(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+
+        let mut src_read = s.src.len();
+        let mut msg_read = s.msg.len();
+
         let ret = unsafe {
             sys::dlpi_recv(
                 s.h.0,
@@ -264,14 +362,210 @@ impl<'a> Future for DlpiRecv<'a> {
         if ret == ResultCode::Success as i32 {
             Poll::Ready(Ok((src_read, msg_read)))
         } else if ret == ResultCode::ETimedout as i32 {
-            cx.waker().wake_by_ref();
-            Poll::Pending
+            guard.clear_ready();
+            drop(guard);
+            // Poll readiness again so the reactor stores this task's waker;
+            // if the fd became readable in the meantime, ask to be polled
+            // again.
+            match s.afd.as_ref().unwrap().poll_read_ready(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(_) => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
         } else {
             Poll::Ready(Err(to_io_error(ret)))
         }
     }
 }
 
+/// A link-change event delivered to a callback registered with
+/// [`enable_notify`], or produced by a [`NotifyStream`].
+#[derive(Debug, Clone)]
+pub enum NotifyInfo {
+    /// The link has gone down.
+    LinkDown,
+    /// The link has come up.
+    LinkUp,
+    /// The link speed has changed, in Mbps.
+    Speed(u64),
+    /// The link's physical (MAC) address has changed.
+    PhysAddr(Vec<u8>),
+    /// The link's maximum SDU (MTU) has changed.
+    SduSize(u32),
+    /// Another handle has enabled physical-level promiscuous mode.
+    PromiscOnPhys,
+    /// Another handle has disabled physical-level promiscuous mode.
+    PromiscOffPhys,
+}
+
+impl NotifyInfo {
+    /// `dni_note` is a bitmask, so a single callback invocation can report
+    /// more than one event at once; decode every bit that is set rather
+    /// than matching `dni_note` for exact equality.
+    fn from_raw(raw: &sys::dlpi_notifyinfo_t) -> Vec<Self> {
+        let mut events = Vec::new();
+
+        if raw.dni_note & sys::DL_NOTE_LINK_DOWN != 0 {
+            events.push(NotifyInfo::LinkDown);
+        }
+        if raw.dni_note & sys::DL_NOTE_LINK_UP != 0 {
+            events.push(NotifyInfo::LinkUp);
+        }
+        if raw.dni_note & sys::DL_NOTE_SPEED != 0 {
+            events.push(NotifyInfo::Speed(raw.dni_speed as u64));
+        }
+        if raw.dni_note & sys::DL_NOTE_PHYS_ADDR != 0 {
+            let len = raw.dni_physaddrlen as usize;
+            events.push(NotifyInfo::PhysAddr(
+                raw.dni_physaddr[..len].to_vec(),
+            ));
+        }
+        if raw.dni_note & sys::DL_NOTE_SDU_SIZE != 0 {
+            events.push(NotifyInfo::SduSize(raw.dni_size));
+        }
+        if raw.dni_note & sys::DL_NOTE_PROMISC_ON_PHYS != 0 {
+            events.push(NotifyInfo::PromiscOnPhys);
+        }
+        if raw.dni_note & sys::DL_NOTE_PROMISC_OFF_PHYS != 0 {
+            events.push(NotifyInfo::PromiscOffPhys);
+        }
+
+        events
+    }
+}
+
+struct NotifyCallback {
+    cb: Box<dyn FnMut(&NotifyInfo) + Send>,
+}
+
+unsafe extern "C" fn notify_trampoline(
+    _dh: *mut sys::dlpi_handle_t,
+    info: *mut sys::dlpi_notifyinfo_t,
+    arg: *mut c_void,
+) {
+    if info.is_null() || arg.is_null() {
+        return;
+    }
+    let cb = &mut *(arg as *mut NotifyCallback);
+    for ni in NotifyInfo::from_raw(&*info) {
+        (cb.cb)(&ni);
+    }
+}
+
+/// Subscription handle returned by [`enable_notify`]. Pass to
+/// [`disable_notify`] to stop delivering events and free the callback.
+pub struct NotifyId {
+    id: sys::dlpi_notifyid_t,
+    arg: *mut c_void,
+}
+unsafe impl Send for NotifyId {}
+
+/// Subscribe to asynchronous link-change notifications on `h`. `mask` is a
+/// bitwise-OR of `sys::DL_NOTE_*` flags selecting which events to deliver.
+///
+/// The callback is driven by `libdlpi` whenever the underlying stream is
+/// serviced (e.g. via [`recv`] or [`recv_async`]), so it must not block.
+/// Prefer [`notifications`] for an async-friendly interface.
+pub fn enable_notify(
+    h: DlpiHandle,
+    mask: u32,
+    cb: impl FnMut(&NotifyInfo) + Send + 'static,
+) -> Result<NotifyId> {
+    let arg = Box::into_raw(Box::new(NotifyCallback { cb: Box::new(cb) }))
+        as *mut c_void;
+
+    let mut id: sys::dlpi_notifyid_t = ptr::null_mut();
+    let ret = unsafe {
+        sys::dlpi_enabnotify(h.0, mask, notify_trampoline, arg, &mut id)
+    };
+
+    if let Err(e) = check_return(ret) {
+        unsafe { drop(Box::from_raw(arg as *mut NotifyCallback)) };
+        return Err(e);
+    }
+
+    Ok(NotifyId { id, arg })
+}
+
+/// Cancel a subscription previously established with [`enable_notify`].
+pub fn disable_notify(h: DlpiHandle, notify: NotifyId) -> Result<()> {
+    let mut argp = ptr::null_mut();
+    let ret = unsafe { sys::dlpi_disabnotify(h.0, notify.id, &mut argp) };
+    check_return(ret)?;
+    unsafe { drop(Box::from_raw(notify.arg as *mut NotifyCallback)) };
+    Ok(())
+}
+
+/// An async stream of [`NotifyInfo`] events, returned by [`notifications`].
+///
+/// **This stream must own its handle exclusively.** Servicing it drives
+/// `libdlpi`'s own `dlpi_recv`, which dequeues whatever message is next on
+/// the stream; if a data frame is next instead of a notification, that
+/// frame is consumed and lost. Never call [`recv`]/[`recv_async`] against
+/// the same handle as a live [`NotifyStream`] — open a second handle to the
+/// link for data traffic instead.
+pub struct NotifyStream {
+    h: DlpiHandle,
+    notify_id: Option<NotifyId>,
+    queue: Arc<Mutex<VecDeque<NotifyInfo>>>,
+    afd: Option<AsyncFd<RawFd>>,
+}
+
+impl NotifyStream {
+    /// Wait for the next link-change event, servicing the stream on the
+    /// same reactor integration [`recv_async`] uses.
+    pub async fn next(&mut self) -> Result<NotifyInfo> {
+        loop {
+            if let Some(ni) = self.queue.lock().unwrap().pop_front() {
+                return Ok(ni);
+            }
+
+            if self.afd.is_none() {
+                self.afd = Some(AsyncFd::new(fd(self.h)?)?);
+            }
+
+            let mut guard = self.afd.as_mut().unwrap().readable().await?;
+
+            // Servicing the stream is what causes libdlpi to invoke any
+            // pending notify callback; a timeout here just means no
+            // notification happened to be pending. This handle must not
+            // be shared with a data recv/recv_async caller — see the
+            // struct-level warning on `NotifyStream`.
+            let mut src = [0u8; sys::DLPI_PHYSADDR_MAX];
+            let mut msg = [0u8; sys::DLPI_PHYSADDR_MAX];
+            let _ = recv(self.h, &mut src, &mut msg, 0, None);
+            guard.clear_ready();
+        }
+    }
+}
+
+impl Drop for NotifyStream {
+    fn drop(&mut self) {
+        if let Some(notify_id) = self.notify_id.take() {
+            let _ = disable_notify(self.h, notify_id);
+        }
+    }
+}
+
+/// Subscribe to asynchronous link-change notifications on `h` as a stream,
+/// using the same reactor integration as [`recv_async`]. `mask` is a
+/// bitwise-OR of `sys::DL_NOTE_*` flags selecting which events to deliver.
+///
+/// See the warning on [`NotifyStream`]: `h` must not be used for
+/// [`recv`]/[`recv_async`] while the returned stream is alive.
+pub fn notifications(h: DlpiHandle, mask: u32) -> Result<NotifyStream> {
+    let queue = Arc::new(Mutex::new(VecDeque::new()));
+    let q = queue.clone();
+    let notify_id =
+        enable_notify(h, mask, move |ni: &NotifyInfo| {
+            q.lock().unwrap().push_back(ni.clone());
+        })?;
+
+    Ok(NotifyStream { h, notify_id: Some(notify_id), queue, afd: None })
+}
+
 /// Bind a DLPI link to a service access point type.
 ///
 /// This will restrict the DLPI link to only operate on the provided service
@@ -312,24 +606,88 @@ pub fn disable_multicast(h: DlpiHandle, addr: &[u8]) -> Result<()> {
     Ok(())
 }
 
-/// Enable promiscuous mode for the specified handle. See DL_PROMISC_* for
-/// levels.
-pub fn promisc_on(h: DlpiHandle, level: u32) -> Result<()> {
-    let ret = unsafe { sys::dlpi_promiscon(h.0, level) };
-    match ret {
-        -1 => Err(Error::from_raw_os_error(libc::EINVAL)),
-        _ => Ok(()),
-    }
+/// Distinguishes a link's current physical address from its
+/// factory-assigned one. See [`physaddr`] and [`set_physaddr`].
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PhysAddrType {
+    /// The address currently in use by the link.
+    Current = sys::DL_CURR_PHYS_ADDR,
+    /// The address the link shipped with, ignoring any override from
+    /// [`set_physaddr`].
+    Factory = sys::DL_FACT_PHYS_ADDR,
 }
 
-/// Disable promiscuous mode for the specified handle. See DL_PROMISC_* for
-/// levels.
-pub fn promisc_off(h: DlpiHandle, level: u32) -> Result<()> {
-    let ret = unsafe { sys::dlpi_promiscoff(h.0, level) };
-    match ret {
-        -1 => Err(Error::from_raw_os_error(libc::EINVAL)),
-        _ => Ok(()),
-    }
+/// Get the physical (MAC) address of the link associated with the provided
+/// handle.
+///
+/// Link-layer agents (DHCP clients, raw frame builders, etc.) need this as
+/// the source address field of frames they assemble.
+pub fn physaddr(h: DlpiHandle, which: PhysAddrType) -> Result<Vec<u8>> {
+    let mut addr = [0u8; sys::DLPI_PHYSADDR_MAX];
+    let mut addrlen = addr.len();
+    let ret = unsafe {
+        sys::dlpi_get_physaddr(
+            h.0,
+            which as u32,
+            addr.as_mut_ptr() as *mut c_void,
+            &mut addrlen,
+        )
+    };
+
+    check_return(ret)?;
+    Ok(addr[..addrlen].to_vec())
+}
+
+/// Override the current physical (MAC) address of the link associated with
+/// the provided handle.
+pub fn set_physaddr(h: DlpiHandle, addr: &[u8]) -> Result<()> {
+    let ret = unsafe {
+        sys::dlpi_set_physaddr(
+            h.0,
+            sys::DL_CURR_PHYS_ADDR,
+            addr.as_ptr() as *const c_void,
+            addr.len(),
+        )
+    };
+
+    check_return(ret)?;
+    Ok(())
+}
+
+/// Promiscuous mode level. See `dlpi_promiscon(3DLPI)`.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PromiscLevel {
+    /// Promiscuous at the physical level: see every frame on the wire,
+    /// regardless of destination address.
+    Phys = sys::DL_PROMISC_PHYS,
+    /// Promiscuous at the SAP level: see frames for every service access
+    /// point, not just the one this handle is bound to.
+    Sap = sys::DL_PROMISC_SAP,
+    /// Promiscuous for multicast traffic: see all multicast frames,
+    /// regardless of which groups have been joined.
+    Multi = sys::DL_PROMISC_MULTI,
+    /// Receive-only promiscuous mode: see frames this handle would
+    /// otherwise receive, without affecting what is sent (illumos only).
+    RxOnly = sys::DL_PROMISC_RX_ONLY,
+    /// Apply loopback fixups to promiscuously received packets, so locally
+    /// generated traffic looks like it does on the wire (illumos only).
+    Fixups = sys::DL_PROMISC_FIXUPS,
+}
+
+/// Enable promiscuous mode for the specified handle at the given level.
+pub fn promisc_on(h: DlpiHandle, level: PromiscLevel) -> Result<()> {
+    let ret = unsafe { sys::dlpi_promiscon(h.0, level as u32) };
+    check_return(ret)?;
+    Ok(())
+}
+
+/// Disable promiscuous mode for the specified handle at the given level.
+pub fn promisc_off(h: DlpiHandle, level: PromiscLevel) -> Result<()> {
+    let ret = unsafe { sys::dlpi_promiscoff(h.0, level as u32) };
+    check_return(ret)?;
+    Ok(())
 }
 
 /// Get a file descriptor associated with the provided handle.
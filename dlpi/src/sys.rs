@@ -33,6 +33,31 @@ pub const DL_PROMISC_SAP: c_uint = 0x02;
 pub const DL_PROMISC_MULTI: c_uint = 0x03;
 /// Promiscuous mode for rx only
 pub const DL_PROMISC_RX_ONLY: c_uint = 0x04;
+/// Apply loopback fixups to promiscuously received packets (illumos only)
+pub const DL_PROMISC_FIXUPS: c_uint = 0x05;
+
+/// Link's physical (MAC) address has changed.
+pub const DL_NOTE_PHYS_ADDR: c_uint = 0x0001;
+/// Another handle has enabled physical-level promiscuous mode.
+pub const DL_NOTE_PROMISC_ON_PHYS: c_uint = 0x0002;
+/// Another handle has disabled physical-level promiscuous mode.
+pub const DL_NOTE_PROMISC_OFF_PHYS: c_uint = 0x0004;
+/// Link has gone down.
+pub const DL_NOTE_LINK_DOWN: c_uint = 0x0008;
+/// Link has come up.
+pub const DL_NOTE_LINK_UP: c_uint = 0x0010;
+/// Link's maximum SDU (MTU) has changed.
+pub const DL_NOTE_SDU_SIZE: c_uint = 0x0080;
+/// Link speed or duplex has changed.
+pub const DL_NOTE_SPEED: c_uint = 0x0100;
+
+/// The link's factory physical (MAC) address.
+pub const DL_FACT_PHYS_ADDR: c_uint = 0x01;
+/// The link's current physical (MAC) address.
+pub const DL_CURR_PHYS_ADDR: c_uint = 0x02;
+
+/// Ethernet Bus MAC type, as reported in `dlpi_info_t::di_mactype`.
+pub const DL_ETHER: c_uint = 0x04;
 
 /// DLPI operation succeeded
 pub const DLPI_SUCCESS: c_int = 10000;
@@ -120,12 +145,166 @@ pub struct dl_priority_t {
 /// Indicates a non-DLPI specific system error in a DLPI call.
 pub const DL_SYSERR: c_int = 0x04;
 
+/// Maximum length of a DLPI link name.
+pub const DLPI_LINKNAME_MAX: usize = 256;
+
+/// Selected connectionless QOS parameters, as embedded in `dlpi_info_t`.
+/// This crate does not interpret QOS parameters; the field exists only so
+/// `dlpi_info_t` has the correct size for `dlpi_info()` to write into.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct dl_qos_cl_sel1_t {
+    pub dl_qos_type: c_uint,
+    pub dl_trans_delay: c_uint,
+    pub dl_protection: c_uint,
+    pub dl_residual_error: c_uint,
+    pub dl_priority: c_uint,
+}
+
+/// Range of available connectionless QOS parameters, as embedded in
+/// `dlpi_info_t`. This crate does not interpret QOS parameters; the field
+/// exists only so `dlpi_info_t` has the correct size for `dlpi_info()` to
+/// write into.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct dl_qos_cl_range1_t {
+    pub dl_qos_type: c_uint,
+    pub dl_trans_delay_target: c_uint,
+    pub dl_trans_delay_max_acceptable: c_uint,
+    pub dl_protection1: c_uint,
+    pub dl_protection2: c_uint,
+    pub dl_protection3: c_uint,
+    pub dl_residual_error1: c_uint,
+    pub dl_residual_error2: c_uint,
+    pub dl_residual_error3: c_uint,
+    pub dl_priority_top: c_uint,
+    pub dl_priority_bottom: c_uint,
+}
+
+/// Information describing a bound DLPI link, as returned by `dlpi_info`.
+///
+/// There is no version or bound-SAP field on this struct, and no factory
+/// address field; use [`crate::physaddr`] with
+/// [`crate::PhysAddrType::Factory`] to get the factory address instead.
+#[repr(C)]
+#[derive(Clone)]
+pub struct dlpi_info_t {
+    /// Options the link was opened with, a bitwise-OR of `DLPI_*` flags.
+    pub di_opts: c_int,
+    /// Maximum service data unit.
+    pub di_max_sdu: c_uint,
+    /// Minimum service data unit.
+    pub di_min_sdu: c_uint,
+    /// Current DLPI state.
+    pub di_state: c_uint,
+    /// DLPI MAC type, e.g. `DL_ETHER`.
+    pub di_mactype: c_uint,
+    /// Name of the link.
+    pub di_linkname: [c_char; DLPI_LINKNAME_MAX],
+    /// Current physical address.
+    pub di_physaddr: [c_uchar; DLPI_PHYSADDR_MAX],
+    /// Length of `di_physaddr`.
+    pub di_physaddrlen: c_uint,
+    /// Broadcast address.
+    pub di_bcastaddr: [c_uchar; DLPI_PHYSADDR_MAX],
+    /// Length of `di_bcastaddr`.
+    pub di_bcastaddrlen: c_uint,
+    /// Length of the SAP field within an address.
+    pub di_sap_length: c_uint,
+    /// Timeout used by `dlpi_recv`.
+    pub di_timeout: c_uint,
+    /// Negotiated connectionless QOS parameters.
+    pub di_qos_sel: dl_qos_cl_sel1_t,
+    /// Available connectionless QOS parameter ranges.
+    pub di_qos_range: dl_qos_cl_range1_t,
+}
+
+impl Default for dlpi_info_t {
+    fn default() -> Self {
+        dlpi_info_t {
+            di_opts: 0,
+            di_max_sdu: 0,
+            di_min_sdu: 0,
+            di_state: 0,
+            di_mactype: 0,
+            di_linkname: [0; DLPI_LINKNAME_MAX],
+            di_physaddr: [0; DLPI_PHYSADDR_MAX],
+            di_physaddrlen: 0,
+            di_bcastaddr: [0; DLPI_PHYSADDR_MAX],
+            di_bcastaddrlen: 0,
+            di_sap_length: 0,
+            di_timeout: 0,
+            di_qos_sel: dl_qos_cl_sel1_t {
+                dl_qos_type: 0,
+                dl_trans_delay: 0,
+                dl_protection: 0,
+                dl_residual_error: 0,
+                dl_priority: 0,
+            },
+            di_qos_range: dl_qos_cl_range1_t {
+                dl_qos_type: 0,
+                dl_trans_delay_target: 0,
+                dl_trans_delay_max_acceptable: 0,
+                dl_protection1: 0,
+                dl_protection2: 0,
+                dl_protection3: 0,
+                dl_residual_error1: 0,
+                dl_residual_error2: 0,
+                dl_residual_error3: 0,
+                dl_priority_top: 0,
+                dl_priority_bottom: 0,
+            },
+        }
+    }
+}
+
 /// Opaque handle to a DLPI link instance.
 #[derive(Clone)]
 pub enum dlpi_handle_t {}
 unsafe impl Send for dlpi_handle_t {}
 unsafe impl Sync for dlpi_handle_t {}
 
+/// Opaque identifier for a notification registered with `dlpi_enabnotify`.
+pub type dlpi_notifyid_t = *mut c_void;
+
+/// Event payload delivered to a callback registered with `dlpi_enabnotify`.
+///
+/// `dni_note` is a bitmask: `libdlpi` may report more than one fired event
+/// in a single callback (e.g. a speed change observed at the same time as
+/// link-up), so callers must test individual `DL_NOTE_*` bits rather than
+/// comparing `dni_note` for equality. Unlike the other `DL_NOTE_*` payload
+/// fields, `dni_physaddr`/`dni_physaddrlen` are flat members of this struct,
+/// not a union member selected by `dni_note`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct dlpi_notifyinfo_t {
+    /// Bitmask of the `DL_NOTE_*` events that fired.
+    pub dni_note: c_uint,
+    /// New link speed in Mbps, valid when `DL_NOTE_SPEED` is set.
+    pub dni_speed: c_uint,
+    /// New maximum SDU size, valid when `DL_NOTE_SDU_SIZE` is set.
+    pub dni_size: c_uint,
+    /// New physical address, valid when `DL_NOTE_PHYS_ADDR` is set.
+    pub dni_physaddr: [c_uchar; DLPI_PHYSADDR_MAX],
+    /// Length of `dni_physaddr`.
+    pub dni_physaddrlen: c_uint,
+}
+
+/// Callback signature invoked by `libdlpi` when a subscribed notification
+/// fires. The final argument is the opaque `arg` pointer passed to
+/// `dlpi_enabnotify`.
+pub type dlpi_notify_cb = unsafe extern "C" fn(
+    *mut dlpi_handle_t,
+    *mut dlpi_notifyinfo_t,
+    *mut c_void,
+);
+
+/// Callback signature invoked by `dlpi_walk` once per DLPI-capable link.
+/// Returns `boolean_t`: return zero (`B_FALSE`) to keep walking, nonzero
+/// (`B_TRUE`) to stop early.
+pub type dlpi_walk_cb =
+    unsafe extern "C" fn(*const c_char, *mut c_void) -> c_int;
+
 extern "C" {
     /// Creates an instance of the DLPI version 2 link named by linnkname.
     ///
@@ -201,6 +380,63 @@ extern "C" {
     /// open DLPI stream associated with the provided handle.
     pub fn dlpi_fd(dh: *mut dlpi_handle_t) -> i32;
 
+    /// Retrieves information about the DLPI link associated with the
+    /// provided handle, such as its MAC type, physical address, and SDU
+    /// bounds.
+    pub fn dlpi_info(
+        dh: *mut dlpi_handle_t,
+        infop: *mut dlpi_info_t,
+        dlpi_flags: c_uint,
+    ) -> i32;
+
+    /// Registers `notifyfunc` to be invoked whenever one of the `notes`
+    /// events (a bitwise-OR of `DL_NOTE_*`) occurs on `dh`. On success,
+    /// `idp` is filled in with an id usable with `dlpi_disabnotify`.
+    pub fn dlpi_enabnotify(
+        dh: *mut dlpi_handle_t,
+        notes: c_uint,
+        notifyfunc: dlpi_notify_cb,
+        arg: *mut c_void,
+        idp: *mut dlpi_notifyid_t,
+    ) -> i32;
+
+    /// Unregisters a notification previously registered with
+    /// `dlpi_enabnotify`. On success, `argp` is filled in with the `arg`
+    /// pointer that was passed to `dlpi_enabnotify`.
+    pub fn dlpi_disabnotify(
+        dh: *mut dlpi_handle_t,
+        id: dlpi_notifyid_t,
+        argp: *mut *mut c_void,
+    ) -> i32;
+
+    /// Invokes `walkfunc` once for each DLPI-capable link on the system,
+    /// passing the link name and the opaque `arg` pointer. Stops early if
+    /// `walkfunc` returns `B_TRUE` (nonzero).
+    pub fn dlpi_walk(
+        walkfunc: dlpi_walk_cb,
+        arg: *mut c_void,
+        flags: c_uint,
+    );
+
+    /// Retrieves the physical (MAC) address of `type_` (`DL_CURR_PHYS_ADDR`
+    /// or `DL_FACT_PHYS_ADDR`) into `addrp`, updating `addrlenp` with the
+    /// number of bytes written.
+    pub fn dlpi_get_physaddr(
+        dh: *mut dlpi_handle_t,
+        type_: c_uint,
+        addrp: *mut c_void,
+        addrlenp: *mut usize,
+    ) -> i32;
+
+    /// Sets the current physical (MAC) address to the `addrlen` bytes at
+    /// `addrp`. `type_` must be `DL_CURR_PHYS_ADDR`.
+    pub fn dlpi_set_physaddr(
+        dh: *mut dlpi_handle_t,
+        type_: c_uint,
+        addrp: *const c_void,
+        addrlen: usize,
+    ) -> i32;
+
 }
 
 /// A convenience method for creating a null dlpi handle to be later initialized
@@ -0,0 +1,83 @@
+use crate::capture::{linktype_for, write_global_header, write_packet};
+use crate::sys::{self, dlpi_notifyinfo_t, DLPI_PHYSADDR_MAX};
+use crate::NotifyInfo;
+use std::time::Duration;
+
+fn notifyinfo(note: u32) -> dlpi_notifyinfo_t {
+    dlpi_notifyinfo_t {
+        dni_note: note,
+        dni_speed: 1000,
+        dni_size: 1500,
+        dni_physaddr: [0xaa; DLPI_PHYSADDR_MAX],
+        dni_physaddrlen: 6,
+    }
+}
+
+#[test]
+fn notifyinfo_decodes_single_events() {
+    let raw = notifyinfo(sys::DL_NOTE_LINK_UP);
+    let events = NotifyInfo::from_raw(&raw);
+    assert!(matches!(events.as_slice(), [NotifyInfo::LinkUp]));
+
+    let raw = notifyinfo(sys::DL_NOTE_SPEED);
+    let events = NotifyInfo::from_raw(&raw);
+    assert!(matches!(events.as_slice(), [NotifyInfo::Speed(1000)]));
+
+    let raw = notifyinfo(sys::DL_NOTE_PHYS_ADDR);
+    let events = NotifyInfo::from_raw(&raw);
+    match events.as_slice() {
+        [NotifyInfo::PhysAddr(addr)] => assert_eq!(addr, &[0xaa; 6]),
+        other => panic!("unexpected decode: {:?}", other),
+    }
+}
+
+#[test]
+fn notifyinfo_decodes_combined_events() {
+    let raw = notifyinfo(sys::DL_NOTE_LINK_UP | sys::DL_NOTE_SPEED);
+    let events = NotifyInfo::from_raw(&raw);
+    assert!(matches!(
+        events.as_slice(),
+        [NotifyInfo::LinkUp, NotifyInfo::Speed(1000)]
+    ));
+}
+
+#[test]
+fn notifyinfo_decodes_no_events() {
+    let raw = notifyinfo(0);
+    assert!(NotifyInfo::from_raw(&raw).is_empty());
+}
+
+#[test]
+fn linktype_for_known_and_unknown_mac_types() {
+    assert_eq!(linktype_for(sys::DL_ETHER), 1); // LINKTYPE_ETHERNET
+    assert_eq!(linktype_for(0xdead), 101); // LINKTYPE_RAW fallback
+}
+
+#[test]
+fn global_header_byte_layout() {
+    let mut buf = Vec::new();
+    write_global_header(&mut buf, 65535, 1).unwrap();
+
+    assert_eq!(buf.len(), 24);
+    assert_eq!(&buf[0..4], &0xa1b2c3d4u32.to_le_bytes()); // magic
+    assert_eq!(&buf[4..6], &2u16.to_le_bytes()); // version major
+    assert_eq!(&buf[6..8], &4u16.to_le_bytes()); // version minor
+    assert_eq!(&buf[8..12], &0i32.to_le_bytes()); // thiszone
+    assert_eq!(&buf[12..16], &0u32.to_le_bytes()); // sigfigs
+    assert_eq!(&buf[16..20], &65535u32.to_le_bytes()); // snaplen
+    assert_eq!(&buf[20..24], &1u32.to_le_bytes()); // linktype
+}
+
+#[test]
+fn packet_record_byte_layout() {
+    let mut buf = Vec::new();
+    let data = [1u8, 2, 3, 4];
+    write_packet(&mut buf, Duration::new(5, 6_000), &data, 9).unwrap();
+
+    assert_eq!(buf.len(), 16 + data.len());
+    assert_eq!(&buf[0..4], &5u32.to_le_bytes()); // ts seconds
+    assert_eq!(&buf[4..8], &6u32.to_le_bytes()); // ts microseconds
+    assert_eq!(&buf[8..12], &(data.len() as u32).to_le_bytes()); // incl len
+    assert_eq!(&buf[12..16], &9u32.to_le_bytes()); // orig len
+    assert_eq!(&buf[16..], &data[..]);
+}